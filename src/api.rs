@@ -0,0 +1,168 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use url::form_urlencoded;
+
+/// Seconds a request to a forge API may take before gpr goes on without it
+const TIMEOUT_SECONDS: &str = "5";
+
+/// Labels GitHub returns per page, the most it allows
+const LABELS_PER_PAGE: usize = 100;
+
+/// Title, description and labels of an existing pull request
+pub struct PullRequest {
+    pub title: String,
+    pub body: Option<String>,
+    pub labels: Vec<String>,
+}
+
+/// Default branch of the GitLab project `path` (like `group/sub/project`) on the
+/// instance at `base_url`, sending `token` as the `PRIVATE-TOKEN` header
+pub fn gitlab_default_branch(
+    base_url: &str,
+    path: &str,
+    token: Option<&str>,
+) -> Result<String, String> {
+    let project: String = form_urlencoded::byte_serialize(path.as_bytes()).collect();
+    let url = format!("{}/api/v4/projects/{}", base_url, project);
+    let headers: Vec<String> = token
+        .map(|token| format!("PRIVATE-TOKEN: {}", token))
+        .into_iter()
+        .collect();
+
+    let project = get_json(&url, &headers)?;
+    project["default_branch"]
+        .as_str()
+        .filter(|branch| !branch.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} has no default branch", url))
+}
+
+/// Names of every label of the GitHub repository `owner/repo`, from the REST API at
+/// `api_url` (https://api.github.com or `<host>/api/v3` on GitHub Enterprise)
+pub fn github_labels(
+    api_url: &str,
+    owner: &str,
+    repo: &str,
+    token: &str,
+) -> Result<Vec<String>, String> {
+    let headers = [
+        format!("Authorization: Bearer {}", token),
+        "Accept: application/vnd.github+json".to_string(),
+    ];
+
+    let mut labels = Vec::new();
+    for page in 1.. {
+        let url = format!(
+            "{}/repos/{}/{}/labels?per_page={}&page={}",
+            api_url, owner, repo, LABELS_PER_PAGE, page
+        );
+        let response = get_json(&url, &headers)?;
+        let Some(page_labels) = response.as_array() else {
+            return Err(format!("{} did not answer with a list of labels", url));
+        };
+
+        labels.extend(
+            page_labels
+                .iter()
+                .filter_map(|label| label["name"].as_str().map(str::to_string)),
+        );
+        if page_labels.len() < LABELS_PER_PAGE {
+            break;
+        }
+    }
+    Ok(labels)
+}
+
+/// Pull request `number` of the GitHub repository `owner/repo`, from the REST API at
+/// `api_url`; public repositories need no `token`
+pub fn github_pull_request(
+    api_url: &str,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    token: Option<&str>,
+) -> Result<PullRequest, String> {
+    let url = format!("{}/repos/{}/{}/pulls/{}", api_url, owner, repo, number);
+    let mut headers = vec!["Accept: application/vnd.github+json".to_string()];
+    headers.extend(token.map(|token| format!("Authorization: Bearer {}", token)));
+
+    let pull = get_json(&url, &headers)?;
+    Ok(PullRequest {
+        title: pull["title"]
+            .as_str()
+            .ok_or_else(|| format!("{} did not answer with a pull request", url))?
+            .to_string(),
+        body: pull["body"].as_str().map(str::to_string),
+        labels: pull["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| label["name"].as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+/// Merge request `number` of the GitLab project `path` on the instance at `base_url`
+pub fn gitlab_merge_request(
+    base_url: &str,
+    path: &str,
+    number: u64,
+    token: Option<&str>,
+) -> Result<PullRequest, String> {
+    let project: String = form_urlencoded::byte_serialize(path.as_bytes()).collect();
+    let url = format!(
+        "{}/api/v4/projects/{}/merge_requests/{}",
+        base_url, project, number
+    );
+    let headers: Vec<String> = token
+        .map(|token| format!("PRIVATE-TOKEN: {}", token))
+        .into_iter()
+        .collect();
+
+    let merge_request = get_json(&url, &headers)?;
+    Ok(PullRequest {
+        title: merge_request["title"]
+            .as_str()
+            .ok_or_else(|| format!("{} did not answer with a merge request", url))?
+            .to_string(),
+        body: merge_request["description"].as_str().map(str::to_string),
+        labels: merge_request["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| label.as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+/// GET `url` with curl and parse the answer as JSON. Headers are passed as curl
+/// config on stdin so tokens stay out of `ps`
+fn get_json(url: &str, headers: &[String]) -> Result<serde_json::Value, String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--fail", "--max-time", TIMEOUT_SECONDS])
+        .args(["--config", "-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+
+    // Dropping stdin closes it, curl reads no further config
+    if let Some(mut stdin) = child.stdin.take() {
+        for header in headers {
+            let header = header.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(stdin, "header = \"{}\"", header)
+                .map_err(|e| format!("could not pass the headers to curl: {}", e))?;
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("{} answered with an error", url));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("{} did not answer with JSON: {}", url, e))
+}
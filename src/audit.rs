@@ -0,0 +1,68 @@
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size after which the audit log is moved to `<file>.1` and a new one is started
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// One generated pull request URL, written as a JSON line
+#[derive(Serialize)]
+pub struct Entry<'a> {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub repo: &'a str,
+    pub branch: &'a str,
+    pub target: &'a str,
+    pub url: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    pub fn new(repo: &'a str, branch: &'a str, target: &'a str, url: &'a str) -> Entry<'a> {
+        Entry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            repo,
+            branch,
+            target,
+            url,
+        }
+    }
+}
+
+/// Append `entry` to the log at `path`, keeping one rotated file once it grows past
+/// `MAX_LOG_BYTES`. Failures only warn, the log must never stop a pull request
+pub fn record(path: &str, entry: &Entry) {
+    let path = expand_home(path);
+    if let Err(e) = append(&path, entry) {
+        eprintln!(
+            "Warning: could not write the audit log {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+fn append(path: &Path, entry: &Entry) -> std::io::Result<()> {
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_BYTES) {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        fs::rename(path, rotated)?;
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+}
+
+/// `path` with a leading `~/` replaced by the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// A program and its arguments that opens a URL
+pub struct OpenerCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl OpenerCommand {
+    fn new(program: &str, args: &[&str]) -> OpenerCommand {
+        OpenerCommand {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    fn spawn(&self) -> std::io::Result<()> {
+        Command::new(&self.program).args(&self.args).spawn()?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for OpenerCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", shell_quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// How the URL is opened, beyond the platform defaults
+pub struct Launch<'a> {
+    /// Command from the config, `{url}` in it is replaced by the URL
+    pub opener: Option<&'a str>,
+    /// Open a private window
+    pub private: bool,
+    /// Browser profile to open the URL in
+    pub profile: Option<&'a str>,
+}
+
+/// Open the URL with the configured opener, else the first candidate program that can
+/// be started. With `private` or a `profile` a known browser is started in that
+/// mode, falling back to a normal open
+pub fn open_url(url: &str, launch: &Launch) -> Result<(), String> {
+    if let Some(command) = configured_command(launch.opener, url)? {
+        return command
+            .spawn()
+            .map_err(|e| format!("Could not run opener '{}': {}", command, e));
+    }
+
+    if launch.private || launch.profile.is_some() {
+        for command in browser_candidates(url, launch.private, launch.profile) {
+            if is_in_path(&command.program) && command.spawn().is_ok() {
+                return Ok(());
+            }
+        }
+        eprintln!(
+            "Warning: no browser with known private mode and profile flags found, opening the URL normally"
+        );
+    }
+
+    for command in opener_candidates(url) {
+        if command.spawn().is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err("Could not find a suitable program to open the URL".to_string())
+}
+
+/// The command `open_url` would run, without running it. `None` when no program is
+/// found, an error when the configured opener cannot be parsed
+pub fn browser_command(url: &str, launch: &Launch) -> Result<Option<OpenerCommand>, String> {
+    if let Some(command) = configured_command(launch.opener, url)? {
+        return Ok(Some(command));
+    }
+
+    let browser_candidates = if launch.private || launch.profile.is_some() {
+        browser_candidates(url, launch.private, launch.profile)
+    } else {
+        Vec::new()
+    };
+
+    Ok(browser_candidates
+        .into_iter()
+        .chain(opener_candidates(url))
+        .find(|command| is_in_path(&command.program)))
+}
+
+/// The configured `opener` for `url`, or `None` without one or when its program is
+/// not installed, so the platform defaults are used
+fn configured_command(opener: Option<&str>, url: &str) -> Result<Option<OpenerCommand>, String> {
+    let Some(opener) = opener else {
+        return Ok(None);
+    };
+
+    let command = configured_opener(opener, url)?;
+    if !is_in_path(&command.program) {
+        let reason = if command.program.contains(std::path::MAIN_SEPARATOR) {
+            "does not exist"
+        } else {
+            "is not in PATH"
+        };
+        eprintln!(
+            "Warning: the configured opener {} {}, using the platform default",
+            command.program, reason
+        );
+        return Ok(None);
+    }
+    Ok(Some(command))
+}
+
+/// The `opener` from the config split into words like a shell would, with `{url}`
+/// replaced or the URL appended when the placeholder is missing
+fn configured_opener(opener: &str, url: &str) -> Result<OpenerCommand, String> {
+    let mut words = split_words(opener)?;
+    if !words.iter().any(|word| word.contains("{url}")) {
+        words.push("{url}".to_string());
+    }
+
+    let mut words = words.into_iter().map(|word| word.replace("{url}", url));
+    let program = words
+        .next()
+        .filter(|program| !program.is_empty())
+        .ok_or_else(|| "The configured opener is empty".to_string())?;
+    Ok(OpenerCommand {
+        program,
+        args: words.collect(),
+    })
+}
+
+/// Whitespace separated words, where single and double quotes group words
+fn split_words(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in the opener '{}'", command));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Known browsers, `$BROWSER` first, with the flags opening a private window and
+/// selecting a profile
+fn browser_candidates(url: &str, private: bool, profile: Option<&str>) -> Vec<OpenerCommand> {
+    let mut browsers: Vec<String> = std::env::var("BROWSER")
+        .ok()
+        .filter(|browser| !browser.is_empty())
+        .into_iter()
+        .collect();
+    browsers.extend(
+        [
+            "firefox",
+            "google-chrome",
+            "google-chrome-stable",
+            "chromium",
+            "chromium-browser",
+            "brave-browser",
+            "microsoft-edge",
+            "chrome",
+            "msedge",
+        ]
+        .iter()
+        .map(|browser| browser.to_string()),
+    );
+
+    browsers
+        .iter()
+        .filter_map(|browser| {
+            let name = Path::new(browser)
+                .file_name()?
+                .to_string_lossy()
+                .to_lowercase();
+            let mut args = Vec::new();
+            if name.contains("firefox") {
+                if let Some(profile) = profile {
+                    args.extend(["-P".to_string(), profile.to_string()]);
+                }
+                if private {
+                    args.push("-private-window".to_string());
+                }
+            } else if name.contains("chrom") || name.contains("brave") || name.contains("edge") {
+                if let Some(profile) = profile {
+                    args.push(format!("--profile-directory={}", profile));
+                }
+                if private && name.contains("edge") {
+                    args.push("--inprivate".to_string());
+                } else if private {
+                    args.push("--incognito".to_string());
+                }
+            } else {
+                return None;
+            }
+            args.push(url.to_string());
+            Some(OpenerCommand {
+                program: browser.clone(),
+                args,
+            })
+        })
+        .collect()
+}
+
+// Platform-specific candidates for opening URLs, in the order they are tried
+#[cfg(target_os = "windows")]
+fn opener_candidates(url: &str) -> Vec<OpenerCommand> {
+    vec![OpenerCommand::new("cmd", &["/C", "start", "", url])]
+}
+
+#[cfg(target_os = "macos")]
+fn opener_candidates(url: &str) -> Vec<OpenerCommand> {
+    vec![OpenerCommand::new("open", &[url])]
+}
+
+#[cfg(target_os = "linux")]
+fn opener_candidates(url: &str) -> Vec<OpenerCommand> {
+    // Try several common Linux browser openers
+    let mut candidates: Vec<OpenerCommand> = ["xdg-open", "gnome-open", "kde-open", "wslview"]
+        .iter()
+        .map(|cmd| OpenerCommand::new(cmd, &[url]))
+        .collect();
+
+    // If all else fails, try to detect if running in WSL and use PowerShell
+    if let Ok(osrelease) = std::fs::read_to_string("/proc/sys/kernel/osrelease") {
+        let osrelease = osrelease.to_lowercase();
+        if osrelease.contains("microsoft") || osrelease.contains("wsl") {
+            candidates.push(OpenerCommand::new(
+                "powershell.exe",
+                &["-Command", &format!("Start-Process '{}'", url)],
+            ));
+        }
+    }
+
+    candidates
+}
+
+// Fallback for other Unix systems
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "linux")))]
+fn opener_candidates(url: &str) -> Vec<OpenerCommand> {
+    // Try a few options that might work on various Unix systems
+    [
+        "xdg-open",
+        "open",
+        "x-www-browser",
+        "firefox",
+        "chromium-browser",
+        "google-chrome",
+    ]
+    .iter()
+    .map(|cmd| OpenerCommand::new(cmd, &[url]))
+    .collect()
+}
+
+fn is_in_path(program: &str) -> bool {
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(program).is_file();
+    }
+
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+
+    std::env::split_paths(&paths).any(|dir| {
+        dir.join(program).is_file()
+            || (cfg!(windows) && dir.join(format!("{}.exe", program)).is_file())
+    })
+}
+
+fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
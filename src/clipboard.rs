@@ -0,0 +1,49 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy `text` to the system clipboard with the first candidate program that accepts it
+pub fn copy(text: &str) -> Result<(), String> {
+    for (program, args) in copier_candidates() {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        if child.wait().is_ok_and(|status| status.success()) && written {
+            return Ok(());
+        }
+    }
+
+    Err("Could not find a suitable program to copy to the clipboard".to_string())
+}
+
+// Platform-specific clipboard programs reading from stdin, in the order they are tried
+#[cfg(target_os = "windows")]
+fn copier_candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![("clip", &[])]
+}
+
+#[cfg(target_os = "macos")]
+fn copier_candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![("pbcopy", &[])]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn copier_candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+        // WSL can reach the Windows clipboard
+        ("clip.exe", &[]),
+    ]
+}
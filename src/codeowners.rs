@@ -0,0 +1,88 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Locations of the CODEOWNERS file, in the order GitHub and GitLab look them up
+const LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+];
+
+/// A CODEOWNERS line: files matching `pattern` belong to `owners`
+pub struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+/// The first CODEOWNERS file found below `root`
+pub fn find(root: &Path) -> Option<PathBuf> {
+    LOCATIONS
+        .iter()
+        .map(|location| root.join(location))
+        .find(|path| path.is_file())
+}
+
+/// Rules of a CODEOWNERS file, skipping comments, blank lines and GitLab
+/// `[Section]` headers. Lines without owners are kept, they unassign the files
+pub fn parse(content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| !line.starts_with('[') && !line.starts_with("^["))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pattern = pattern_regex(parts.next()?)?;
+            Some(Rule {
+                pattern,
+                owners: parts.map(str::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Owners of every file in `paths`, deduplicated in the order they are first found.
+/// As in CODEOWNERS itself, the last rule matching a file wins
+pub fn owners(rules: &[Rule], paths: &[String]) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+    for path in paths {
+        let Some(rule) = rules.iter().rev().find(|rule| rule.pattern.is_match(path)) else {
+            continue;
+        };
+        for owner in &rule.owners {
+            if !owners.contains(owner) {
+                owners.push(owner.clone());
+            }
+        }
+    }
+    owners
+}
+
+/// Translate a gitignore style pattern to a regex over repository relative paths.
+/// Patterns with a leading or inner `/` are anchored at the root, others match at
+/// any depth, and a match on a directory covers everything below it
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let trimmed = pattern.trim_end_matches('/');
+    let (trimmed, anchored) = match trimmed.strip_prefix("**/") {
+        Some(rest) => (rest, false),
+        None => (trimmed.trim_start_matches('/'), trimmed.contains('/')),
+    };
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str("(?:/.*)?$");
+
+    Regex::new(&regex).ok()
+}
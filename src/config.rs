@@ -0,0 +1,453 @@
+use crate::{Service, SpaceEncoding, exit_code};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+/// Name of the per-repository config file, looked up in the repository root
+pub const REPO_CONFIG_FILE: &str = ".gpr.toml";
+
+/// Written by `gpr config init`, every key commented out with its default
+pub const TEMPLATE: &str = r#"# gpr configuration
+#
+# Settings here apply everywhere; a .gpr.toml in a repository (or any directory
+# above the current one) overrides them, except opener, openers and
+# allowed_repositories, which only this file or --config can set. Run
+# `gpr --dump-config` to see the effective configuration.
+
+# Remote to target when the branch does not track one and --remote is not given
+# prefer_remote = "upstream"
+
+# Your username on the forge, used to filter --my-prs
+# username = "octocat"
+
+# Command opening URLs instead of the platform default, {url} is replaced by the
+# URL (appended when missing). Only read from this file or --config, a .gpr.toml
+# in a repository cannot set it
+# opener = "firefox --new-tab {url}"
+
+# Opener per operating system (linux, macos, windows, freebsd, ...), taking
+# precedence over opener; a program that is not installed falls back to the
+# default. Like opener, a .gpr.toml cannot set these
+# [openers]
+# macos = "open -a 'Firefox Developer Edition' {url}"
+# linux = "/opt/firefox/firefox --new-tab {url}"
+
+# Open pull requests as draft unless --ready is given
+# draft = false
+
+# Title prefix marking drafts on services that read it from the title (GitLab),
+# e.g. "[WIP]" or "WIP:"
+# draft_marker = "Draft:"
+
+# Text appended to every pull request description, unless --footer is given
+# footer = "See CONTRIBUTING.md before requesting a review"
+
+# Ask before opening more browser tabs than this at once, e.g. with --stack or
+# --all-remotes; without a terminal gpr refuses unless --yes is given
+# max_tabs = 1
+
+# Link copied by --copy-markdown; {owner}, {repo}, {branch}, {target} and {url}
+# are replaced
+# markdown_link = "[{owner}/{repo} #{branch}]({url})"
+
+# Conventional commit types --amend-title-prefix picks from, most significant
+# first
+# title_prefix_types = ["feat", "fix", "perf", "refactor", "revert", "docs", "test", "build", "ci", "style", "chore"]
+
+# Append a JSON line for every generated pull request URL to this file, which is
+# rotated to <file>.1 at 1 MiB; off unless set
+# audit_log = "~/.local/state/gpr/audit.log"
+
+# SSH host aliases from ~/.ssh/config standing for a real host, e.g. a second
+# GitHub account. `account` is your username there (used by --my-prs) and
+# `browser_profile` the Firefox or Chromium profile to open its pages in
+# [host_aliases."github.com-work"]
+# host = "github.com"
+# account = "octocat-at-work"
+# browser_profile = "Work"
+
+# Repositories pull requests may be opened against without asking, as a host,
+# host/owner or host/owner/repo; anything else needs confirmation. Off while empty,
+# only read from this file or --config
+# allowed_repositories = ["github.com/my-org", "git.example.com"]
+
+# Repositories that always need confirmation, even when allowed above; lists from
+# every file, .gpr.toml included, are combined
+# denied_repositories = ["github.com/my-org/production"]
+
+# Service for self-hosted hosts
+# [host_services]
+# "git.example.com" = "gitlab"
+
+# Target branch per branch name pattern, the first matching rule wins and rules
+# in a nearer file come first. `*` stays within one path segment, `**` does not.
+# These take precedence over host_targets and service_targets
+# [[branch_targets]]
+# branch = "release/*"
+# target = "main"
+#
+# [[branch_targets]]
+# branch = "hotfix/**"
+# target = "production"
+
+# Default target branch per service (github, gitlab, bitbucket, azure)
+# [service_targets]
+# gitlab = "develop"
+
+# Default target branch per remote host, takes precedence over service_targets
+# [host_targets]
+# "git.example.com" = "develop"
+
+# Encoding of spaces in query parameters per service: "plus" or "percent"
+# [space_encoding]
+# gitlab = "percent"
+"#;
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    /// Remote preferred when `--remote` is not given (defaults to "upstream")
+    pub prefer_remote: Option<String>,
+
+    /// Your username on the forge, used to filter `--my-prs`
+    pub username: Option<String>,
+
+    /// Command opening URLs, with `{url}` replaced by the URL; never from a `.gpr.toml`
+    pub opener: Option<String>,
+
+    /// Command opening URLs per operating system, taking precedence over `opener`;
+    /// never from a `.gpr.toml`
+    pub openers: BTreeMap<String, String>,
+
+    /// Open pull requests as draft unless `--ready` is given
+    pub draft: Option<bool>,
+
+    /// Title prefix marking drafts on services that read it from the title
+    pub draft_marker: Option<String>,
+
+    /// Text appended to every pull request description
+    pub footer: Option<String>,
+
+    /// Browser tabs opened at once without asking, unless `--yes` is given
+    pub max_tabs: Option<usize>,
+
+    /// Format of the link copied by `--copy-markdown`
+    pub markdown_link: Option<String>,
+
+    /// Conventional commit types `--amend-title-prefix` picks from, most significant first
+    pub title_prefix_types: Option<Vec<String>>,
+
+    /// File every generated pull request URL is logged to
+    pub audit_log: Option<String>,
+
+    /// SSH host aliases standing for a real host, keyed by the alias
+    pub host_aliases: BTreeMap<String, HostAlias>,
+
+    /// Repositories opened without asking, as `host`, `host/owner` or `host/owner/repo`;
+    /// never from a `.gpr.toml`
+    pub allowed_repositories: Vec<String>,
+
+    /// Repositories that always need confirmation, in the same form
+    pub denied_repositories: Vec<String>,
+
+    /// Service per remote host, so self-hosted instances do not need `--service`
+    pub host_services: BTreeMap<String, Service>,
+
+    /// Target branch per branch name pattern, the first matching rule wins
+    pub branch_targets: Vec<BranchTarget>,
+
+    /// Default target branch per service (github, gitlab, bitbucket, azure)
+    pub service_targets: BTreeMap<String, String>,
+
+    /// Default target branch per remote host, takes precedence over `service_targets`
+    pub host_targets: BTreeMap<String, String>,
+
+    /// Encoding of spaces in query parameters per service
+    pub space_encoding: BTreeMap<String, SpaceEncoding>,
+}
+
+/// A host alias like `github.com-work` used in remotes to pick an SSH key
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HostAlias {
+    /// The host the alias stands for
+    pub host: String,
+
+    /// Your username on that host
+    pub account: Option<String>,
+
+    /// Browser profile to open the host's pages in
+    pub browser_profile: Option<String>,
+}
+
+/// A `[[branch_targets]]` rule: branches matching `branch` are merged into `target`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BranchTarget {
+    /// Glob over the branch name, `*` matches within one segment and `**` across `/`
+    pub branch: String,
+
+    pub target: String,
+}
+
+impl BranchTarget {
+    pub fn matches(&self, branch: &str) -> bool {
+        glob_matches(&self.branch, branch)
+    }
+
+    /// Target of the first rule in `rules` matching `branch`
+    pub fn find<'a>(rules: &'a [BranchTarget], branch: &str) -> Option<&'a str> {
+        rules
+            .iter()
+            .find(|rule| rule.matches(branch))
+            .map(|rule| rule.target.as_str())
+    }
+}
+
+impl Config {
+    /// Load the global config, then the repository root config, then every
+    /// `.gpr.toml` from the outermost directory above `cwd` down to `cwd` itself,
+    /// so the nearest file wins
+    pub fn load(repo_root: Option<&Path>, cwd: &Path) -> Config {
+        let mut config = Config::default();
+
+        if let Some(path) = global_config_path() {
+            config.merge(read_config(&path));
+        }
+
+        let mut paths = discover_config_paths(cwd);
+        if let Some(root) = repo_root {
+            let root_config = root.join(REPO_CONFIG_FILE);
+            if !paths.contains(&root_config) {
+                paths.push(root_config);
+            }
+        }
+
+        for path in paths.iter().rev() {
+            config.merge(read_config(path).map(|found| repository_config(found, path)));
+        }
+
+        config
+    }
+
+    /// Load only the config file at `path`, which has to exist
+    pub fn load_file(path: &Path) -> Config {
+        if !path.is_file() {
+            eprintln!("Config file {} does not exist", path.display());
+            exit(exit_code::USAGE);
+        }
+
+        let mut config = Config::default();
+        config.merge(read_config(path));
+        config
+    }
+
+    /// Opener for the operating system gpr runs on, else the general one
+    pub fn opener(&self) -> Option<&str> {
+        self.openers
+            .get(std::env::consts::OS)
+            .or(self.opener.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Why opening a pull request against `repository` (`host/owner/repo`) needs
+    /// confirmation, or `None` when it does not
+    pub fn repository_objection(&self, repository: &str) -> Option<&'static str> {
+        let listed = |list: &[String]| list.iter().any(|entry| covers(entry, repository));
+        if listed(&self.denied_repositories) {
+            Some("is listed in denied_repositories")
+        } else if !self.allowed_repositories.is_empty() && !listed(&self.allowed_repositories) {
+            Some("is not listed in allowed_repositories")
+        } else {
+            None
+        }
+    }
+
+    /// Render the config as TOML. None of the keys hold secrets yet, anything
+    /// sensitive added later has to be redacted here
+    pub fn dump(&self) -> String {
+        match toml::to_string(self) {
+            Ok(dump) => dump,
+            Err(e) => {
+                eprintln!("Error serializing config: {}", e);
+                exit(exit_code::GENERAL);
+            }
+        }
+    }
+
+    /// Overwrite every value that is set in `other`
+    fn merge(&mut self, other: Option<Config>) {
+        let Some(other) = other else {
+            return;
+        };
+
+        if other.prefer_remote.is_some() {
+            self.prefer_remote = other.prefer_remote;
+        }
+
+        if other.username.is_some() {
+            self.username = other.username;
+        }
+
+        if other.opener.is_some() {
+            self.opener = other.opener;
+        }
+
+        if other.draft.is_some() {
+            self.draft = other.draft;
+        }
+
+        if other.draft_marker.is_some() {
+            self.draft_marker = other.draft_marker;
+        }
+
+        if other.footer.is_some() {
+            self.footer = other.footer;
+        }
+
+        if other.max_tabs.is_some() {
+            self.max_tabs = other.max_tabs;
+        }
+
+        if other.markdown_link.is_some() {
+            self.markdown_link = other.markdown_link;
+        }
+
+        if other.title_prefix_types.is_some() {
+            self.title_prefix_types = other.title_prefix_types;
+        }
+
+        if other.audit_log.is_some() {
+            self.audit_log = other.audit_log;
+        }
+
+        self.openers.extend(other.openers);
+        self.host_aliases.extend(other.host_aliases);
+        self.allowed_repositories.extend(other.allowed_repositories);
+        self.denied_repositories.extend(other.denied_repositories);
+        self.host_services.extend(other.host_services);
+        // Rules of the nearer file are tried first
+        self.branch_targets.splice(0..0, other.branch_targets);
+        self.service_targets.extend(other.service_targets);
+        self.host_targets.extend(other.host_targets);
+        self.space_encoding.extend(other.space_encoding);
+    }
+}
+
+/// Whether the branch name `name` matches `pattern`, where `*` matches within one
+/// path segment, `**` across `/` and `?` one character
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).is_ok_and(|regex| regex.is_match(name))
+}
+
+/// Whether `entry` names `repository` or a host or owner containing it. Forges treat
+/// these paths case-insensitively
+fn covers(entry: &str, repository: &str) -> bool {
+    let entry: Vec<&str> = entry.trim_matches('/').split('/').collect();
+    let repository: Vec<&str> = repository.split('/').collect();
+    entry.len() <= repository.len()
+        && entry
+            .iter()
+            .zip(&repository)
+            .all(|(entry, part)| entry.eq_ignore_ascii_case(part))
+}
+
+/// Write `TEMPLATE` to the global config path, keeping an existing file unless `force`
+pub fn init(force: bool) {
+    let Some(path) = global_config_path() else {
+        eprintln!("Could not determine the config directory, set HOME or XDG_CONFIG_HOME");
+        exit(exit_code::GENERAL);
+    };
+
+    if path.exists() && !force {
+        eprintln!(
+            "Config file {} already exists, pass --force to overwrite it",
+            path.display()
+        );
+        exit(exit_code::GENERAL);
+    }
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, TEMPLATE));
+    if let Err(e) = written {
+        eprintln!("Error writing config file {}: {}", path.display(), e);
+        exit(exit_code::GENERAL);
+    }
+
+    println!("Wrote {}", path.display());
+}
+
+/// `$XDG_CONFIG_HOME/gpr/config.toml`, falling back to `~/.config/gpr/config.toml`
+pub fn global_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("gpr").join("config.toml"))
+}
+
+/// `.gpr.toml` files from `start` upwards, nearest first, like git discovers `.git`.
+/// The walk stops before the home directory and at the filesystem root
+fn discover_config_paths(start: &Path) -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    start
+        .ancestors()
+        .take_while(|dir| Some(*dir) != home.as_deref())
+        .map(|dir| dir.join(REPO_CONFIG_FILE))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// `config` from a `.gpr.toml` found in a directory, which comes with the repository
+/// like any committed file. Commands gpr runs and the repositories it opens without
+/// asking are only taken from the user's own config or `--config`, so they are
+/// dropped with a warning
+fn repository_config(mut config: Config, path: &Path) -> Config {
+    if config.opener.take().is_some() {
+        eprintln!(
+            "Warning: ignoring opener in {}, it is only read from the global config or --config",
+            path.display()
+        );
+    }
+    if !std::mem::take(&mut config.openers).is_empty() {
+        eprintln!(
+            "Warning: ignoring openers in {}, they are only read from the global config or --config",
+            path.display()
+        );
+    }
+    if !std::mem::take(&mut config.allowed_repositories).is_empty() {
+        eprintln!(
+            "Warning: ignoring allowed_repositories in {}, a .gpr.toml can only add denied_repositories",
+            path.display()
+        );
+    }
+    config
+}
+
+fn read_config(path: &Path) -> Option<Config> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("Error parsing config file {}: {}", path.display(), e);
+            exit(exit_code::USAGE);
+        }
+    }
+}
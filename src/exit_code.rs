@@ -0,0 +1,17 @@
+//! Exit codes, stable so wrappers can tell failures apart. Clap exits with `USAGE`
+//! on its own for malformed command lines
+
+/// Any failure without a more specific code, including a declined confirmation
+pub const GENERAL: i32 = 1;
+
+/// Invalid options, option values or config files
+pub const USAGE: i32 = 2;
+
+/// The remote URL cannot be parsed or is on a service gpr does not know
+pub const UNKNOWN_SERVICE: i32 = 3;
+
+/// The repository, a remote, a branch or git push failed
+pub const GIT: i32 = 4;
+
+/// No browser could be started to open the URL
+pub const BROWSER: i32 = 5;
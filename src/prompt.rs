@@ -0,0 +1,42 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Whether stdin is a terminal someone can answer questions on
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Ask the user to pick one of `options` by number. Returns `None` when stdin is
+/// not a terminal or the answer is not a valid choice
+pub fn choose(question: &str, options: &[String]) -> Option<usize> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    eprintln!("{}", question);
+    for (i, option) in options.iter().enumerate() {
+        eprintln!("  {}) {}", i + 1, option);
+    }
+    eprint!("Choice [1-{}]: ", options.len());
+    io::stderr().flush().ok()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok()?;
+    match answer.trim().parse::<usize>() {
+        Ok(n) if (1..=options.len()).contains(&n) => Some(n - 1),
+        _ => None,
+    }
+}
+
+/// Ask a yes/no question, defaulting to no. Returns `None` when stdin is not a terminal
+pub fn confirm(question: &str) -> Option<bool> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    eprint!("{} [y/N]: ", question);
+    io::stderr().flush().ok()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok()?;
+    Some(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,33 @@
+use std::io::{self, IsTerminal};
+
+/// Wrap `url` in an OSC 8 escape so terminals render it as a clickable link
+pub fn hyperlink(url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, url)
+}
+
+/// Whether stdout is a terminal known to render OSC 8 hyperlinks
+pub fn supports_hyperlinks() -> bool {
+    if !io::stdout().is_terminal() {
+        return false;
+    }
+
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+    if matches!(
+        var("TERM_PROGRAM").as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) {
+        return true;
+    }
+
+    // VTE based terminals (GNOME Terminal, Tilix, ...) support them since 0.50
+    if var("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000) {
+        return true;
+    }
+
+    ["WT_SESSION", "KITTY_WINDOW_ID", "KONSOLE_VERSION"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some())
+        || var("TERM").contains("kitty")
+        || var("TERM").contains("alacritty")
+}
@@ -0,0 +1,216 @@
+#![allow(dead_code)]
+
+use git2::{Repository, Signature};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// A temporary git repository with one commit, living below a temporary home
+/// directory so no global git or gpr config leaks into the tests
+pub struct TestRepo {
+    home: TempDir,
+    path: PathBuf,
+    pub repo: Repository,
+}
+
+impl TestRepo {
+    /// A repository with an initial commit on `branch`
+    pub fn new(branch: &str) -> TestRepo {
+        let home = TempDir::new().unwrap();
+        let path = home.path().join("repo");
+        let repo = Repository::init(&path).unwrap();
+        repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+
+        let test_repo = TestRepo { home, path, repo };
+        test_repo.commit("initial commit");
+        test_repo
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    pub fn remote(&self, name: &str, url: &str) -> &TestRepo {
+        self.repo.remote(name, url).unwrap();
+        self
+    }
+
+    /// Commit an empty change on top of HEAD
+    pub fn commit(&self, message: &str) -> git2::Oid {
+        let signature = Signature::now("gpr", "gpr@example.com").unwrap();
+        let tree_id = self.repo.index().unwrap().write_tree().unwrap();
+        let tree = self.repo.find_tree(tree_id).unwrap();
+        let parent = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        self.repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+    }
+
+    /// Create a local branch at HEAD without checking it out
+    pub fn branch(&self, name: &str) -> &TestRepo {
+        let head = self.repo.head().unwrap().peel_to_commit().unwrap();
+        self.repo.branch(name, &head, true).unwrap();
+        self
+    }
+
+    /// Check out an existing local branch
+    pub fn checkout(&self, name: &str) -> &TestRepo {
+        self.repo.set_head(&format!("refs/heads/{}", name)).unwrap();
+        self
+    }
+
+    /// Point the remote-tracking branch `<remote>/<branch>` at HEAD, as if fetched
+    pub fn tracking(&self, remote: &str, branch: &str) -> &TestRepo {
+        let head = self.repo.head().unwrap().target().unwrap();
+        self.repo
+            .reference(
+                &format!("refs/remotes/{}/{}", remote, branch),
+                head,
+                true,
+                "test",
+            )
+            .unwrap();
+        self
+    }
+
+    /// Point `refs/remotes/<remote>/HEAD` at `<remote>/<branch>` the way `git clone`
+    /// leaves it, as a loose symbolic ref file
+    pub fn remote_head(&self, remote: &str, branch: &str) -> &TestRepo {
+        let path = self.repo.path().join("refs/remotes").join(remote);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(
+            path.join("HEAD"),
+            format!("ref: refs/remotes/{}/{}\n", remote, branch),
+        )
+        .unwrap();
+        self
+    }
+
+    /// Write a file relative to the repository root
+    pub fn write(&self, relative: &str, content: &str) -> &TestRepo {
+        let path = self.path.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Write the user's own config file, ~/.config/gpr/config.toml
+    pub fn global_config(&self, content: &str) -> &TestRepo {
+        let path = self.home().join(".config/gpr/config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Write and stage a file, it is part of the next `commit`
+    pub fn stage(&self, relative: &str, content: &str) -> &TestRepo {
+        self.write(relative, content);
+        let mut index = self.repo.index().unwrap();
+        index.add_path(Path::new(relative)).unwrap();
+        index.write().unwrap();
+        self
+    }
+
+    /// Put a script standing in for curl in a directory and return a PATH starting
+    /// with it. It prints `answer` and records its arguments and stdin in
+    /// `curl-args` and `curl-stdin` in the home directory
+    #[cfg(unix)]
+    pub fn fake_curl(&self, answer: &str) -> String {
+        std::fs::write(self.home().join("curl-answer"), answer).unwrap();
+        self.fake_program(
+            "curl",
+            "echo \"$@\" > \"$HOME/curl-args\"\ncat > \"$HOME/curl-stdin\"\ncat \"$HOME/curl-answer\"\n",
+        )
+    }
+
+    /// Put a shell `script` named `name` in a directory and return a PATH starting
+    /// with it
+    #[cfg(unix)]
+    pub fn fake_program(&self, name: &str, script: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = self.home().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let program = bin.join(name);
+        std::fs::write(&program, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        format!("{}:{}", bin.display(), std::env::var("PATH").unwrap())
+    }
+
+    /// gpr in `dir` with a clean environment, ready to run
+    pub fn command(&self, dir: &Path, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gpr"));
+        command
+            .args(args)
+            .current_dir(dir)
+            .env("HOME", self.home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("BROWSER");
+        for (key, _) in std::env::vars() {
+            if key.starts_with("GPR_") {
+                command.env_remove(key);
+            }
+        }
+        command
+    }
+
+    /// Run gpr in `dir` with a clean environment
+    pub fn gpr_in(&self, dir: &Path, args: &[&str]) -> Output {
+        self.command(dir, args).output().unwrap()
+    }
+
+    pub fn gpr(&self, args: &[&str]) -> Output {
+        self.gpr_in(&self.path, args)
+    }
+
+    /// The printed PR URL, skipping the push checks
+    pub fn url(&self, args: &[&str]) -> String {
+        let mut all_args = vec!["--print-only", "--assume-pushed"];
+        all_args.extend_from_slice(args);
+        let output = self.gpr(&all_args);
+        assert_success(&output);
+        stdout(&output).trim().to_string()
+    }
+
+    /// The error printed by a failing run
+    pub fn error(&self, args: &[&str]) -> String {
+        let mut all_args = vec!["--print-only", "--assume-pushed"];
+        all_args.extend_from_slice(args);
+        let output = self.gpr(&all_args);
+        assert!(
+            !output.status.success(),
+            "expected failure, got {}",
+            stdout(&output)
+        );
+        stderr(&output)
+    }
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+pub fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "gpr failed with {}: {}",
+        output.status,
+        stderr(output)
+    );
+}
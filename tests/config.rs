@@ -0,0 +1,314 @@
+mod common;
+
+use common::{TestRepo, assert_success, stderr, stdout};
+
+#[test]
+fn config_init_writes_the_template_once() {
+    let repo = TestRepo::new("feature");
+    let path = repo.home().join(".config/gpr/config.toml");
+
+    let output = repo.gpr(&["config", "init"]);
+    assert_success(&output);
+    assert!(stdout(&output).contains("config.toml"));
+    let template = std::fs::read_to_string(&path).unwrap();
+    assert!(template.contains("# prefer_remote = \"upstream\""));
+
+    std::fs::write(&path, "draft = true\n").unwrap();
+    let output = repo.gpr(&["config", "init"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--force"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "draft = true\n");
+
+    assert_success(&repo.gpr(&["config", "init", "--force"]));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), template);
+
+    // The template only holds comments, so it loads as the default config
+    let output = repo.gpr(&["--dump-config"]);
+    assert_success(&output);
+    assert!(!stdout(&output).contains("prefer_remote"));
+}
+
+#[test]
+fn audit_log_appends_a_line_per_url() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .write(".gpr.toml", "audit_log = \"~/audit.log\"\n");
+
+    repo.url(&[]);
+    repo.url(&["--target", "develop"]);
+
+    let log = std::fs::read_to_string(repo.home().join("audit.log")).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains(r#""branch":"feature","target":"develop","url":"https://github.com/owner/repo/compare/develop...feature?expand=1""#));
+}
+
+#[test]
+fn git_dir_and_config_point_at_explicit_paths() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .write(".gpr.toml", "[service_targets]\ngithub = \"ignored\"\n");
+    let config = repo.home().join("explicit.toml");
+    std::fs::write(&config, "[service_targets]\ngithub = \"release\"\n").unwrap();
+
+    let git_dir = repo.path().display().to_string();
+    let config = config.display().to_string();
+    let args = [
+        "--print-only",
+        "--assume-pushed",
+        "--git-dir",
+        &git_dir,
+        "--config",
+        &config,
+    ];
+    let output = repo.gpr_in(repo.home(), &args);
+    assert_success(&output);
+    assert_eq!(
+        stdout(&output).trim(),
+        "https://github.com/owner/repo/compare/release...feature?expand=1"
+    );
+
+    let output = repo.gpr_in(repo.home(), &["--git-dir", "missing"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr(&output).trim(),
+        "Git directory 'missing' does not exist"
+    );
+
+    let output = repo.gpr(&["--config", "missing.toml"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr(&output).trim(),
+        "Config file missing.toml does not exist"
+    );
+}
+
+#[test]
+fn nested_config_files_are_merged_up_to_the_home_directory() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .write(
+            ".gpr.toml",
+            "footer = \"From the root\"\n[service_targets]\ngithub = \"release\"\n",
+        )
+        .write("sub/.gpr.toml", "[service_targets]\ngithub = \"develop\"\n")
+        .write("sub/dir/file.txt", "");
+    // A file in the home directory is not part of any repository
+    std::fs::write(
+        repo.home().join(".gpr.toml"),
+        "[host_targets]\n\"github.com\" = \"home\"\n",
+    )
+    .unwrap();
+    let url_in = |dir: &str| {
+        let output = repo.gpr_in(&repo.path().join(dir), &["--print-only", "--assume-pushed"]);
+        assert_success(&output);
+        stdout(&output).trim().to_string()
+    };
+
+    // The nearest file wins, the ones above still fill in what it leaves out
+    assert_eq!(
+        url_in("sub/dir"),
+        "https://github.com/owner/repo/compare/develop...feature?expand=1&body=From+the+root"
+    );
+    assert_eq!(
+        url_in("."),
+        "https://github.com/owner/repo/compare/release...feature?expand=1&body=From+the+root"
+    );
+}
+
+#[test]
+fn host_aliases_resolve_the_host_account_and_browser_profile() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@gitlab-work:group/project.git").write(
+        ".gpr.toml",
+        "[host_aliases.gitlab-work]\nhost = \"gitlab.com\"\naccount = \"me-at-work\"\nbrowser_profile = \"Profile 2\"\n",
+    );
+
+    assert!(
+        repo.url(&[])
+            .starts_with("https://gitlab.com/group/project/-/merge_requests/new?")
+    );
+    assert_eq!(
+        repo.url(&["--my-prs"]),
+        "https://gitlab.com/group/project/-/merge_requests?state=opened&author_username=me-at-work"
+    );
+
+    if cfg!(target_os = "linux") {
+        let bin = repo.home().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("google-chrome"), "").unwrap();
+        let output = repo
+            .command(
+                repo.path(),
+                &["--assume-pushed", "--print-browser-command", "--my-prs"],
+            )
+            .env("PATH", &bin)
+            .output()
+            .unwrap();
+        assert_success(&output);
+        assert_eq!(
+            stdout(&output).trim(),
+            "google-chrome '--profile-directory=Profile 2' 'https://gitlab.com/group/project/-/merge_requests?state=opened&author_username=me-at-work'"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn configured_opener_replaces_the_platform_default() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .global_config("opener = \"my-browser --window 'work tab' {url}\"\n");
+    repo.fake_program("my-browser", "");
+    let path = repo.fake_program("open", "");
+    let run = |args: &[&str]| {
+        repo.command(repo.path(), args)
+            .env("PATH", &path)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["--assume-pushed", "--print-browser-command", "--private"]);
+    assert_success(&output);
+    assert_eq!(
+        stdout(&output).trim(),
+        "my-browser --window 'work tab' 'https://github.com/owner/repo/compare/main...feature?expand=1'"
+    );
+
+    repo.global_config("opener = \"open -a Safari\"\n");
+    let output = run(&["--assume-pushed", "--print-browser-command"]);
+    assert!(
+        stdout(&output)
+            .trim()
+            .starts_with("open -a Safari 'https://github.com/")
+    );
+
+    // A program that is not installed falls back to the platform default
+    repo.global_config("opener = \"missing-browser --new-tab\"\n");
+    let output = run(&["--assume-pushed", "--print-browser-command"]);
+    assert!(stderr(&output).contains("the configured opener missing-browser is not in PATH"));
+    assert!(!stdout(&output).contains("missing-browser"));
+
+    repo.global_config("opener = \"my-browser 'work tab {url}\"\n");
+    let output = run(&["--assume-pushed", "--print-browser-command"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stderr(&output).contains("Unterminated quote in the opener"));
+
+    // A .gpr.toml comes with the repository, it cannot choose what gpr runs
+    repo.global_config("")
+        .write(".gpr.toml", "opener = \"sh -c 'touch pwned'\"\n");
+    let output = run(&["--assume-pushed", "--print-browser-command"]);
+    assert!(stderr(&output).contains(&format!(
+        "Warning: ignoring opener in {}",
+        repo.path().join(".gpr.toml").display()
+    )));
+    assert!(!stdout(&output).contains("touch"));
+}
+
+#[cfg(unix)]
+#[test]
+fn opener_per_operating_system_takes_precedence() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .global_config(&format!(
+            "opener = \"generic-browser\"\n[openers]\n{} = \"os-browser --new-tab\"\n",
+            std::env::consts::OS
+        ));
+    let path = repo.fake_program("os-browser", "");
+    let run = || {
+        repo.command(repo.path(), &["--assume-pushed", "--print-browser-command"])
+            .env("PATH", &path)
+            .output()
+            .unwrap()
+    };
+    let output = run();
+    assert!(stdout(&output).starts_with("os-browser --new-tab 'https://github.com/"));
+
+    // A configured path that does not exist falls back to the platform default
+    repo.global_config(&format!(
+        "opener = \"generic-browser\"\n[openers]\n{} = \"/nonexistent/browser\"\n",
+        std::env::consts::OS
+    ));
+    let output = run();
+    assert!(stderr(&output).contains("the configured opener /nonexistent/browser does not exist"));
+    assert!(!stdout(&output).contains("browser"));
+
+    // Neither can a .gpr.toml name one for the operating system
+    repo.global_config("").write(
+        ".gpr.toml",
+        &format!(
+            "[openers]\n{} = \"os-browser --new-tab\"\n",
+            std::env::consts::OS
+        ),
+    );
+    let output = run();
+    assert!(stderr(&output).contains("Warning: ignoring openers in"));
+    assert!(!stdout(&output).contains("os-browser"));
+}
+
+#[test]
+fn failing_opener_leaves_the_url_on_stdout() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .global_config("opener = \"/nonexistent/browser\"\n");
+
+    let output = repo
+        .command(repo.path(), &["--assume-pushed", "--copy-on-fail"])
+        .env("PATH", "")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stderr(&output).contains("Failed to copy to the clipboard"));
+    assert_eq!(
+        stdout(&output).lines().last(),
+        Some("https://github.com/owner/repo/compare/main...feature?expand=1")
+    );
+}
+
+#[test]
+fn repositories_outside_the_allowlist_need_confirmation() {
+    let repo = TestRepo::new("feature");
+    repo.remote("origin", "git@github.com:owner/repo.git")
+        .global_config("allowed_repositories = [\"GitHub.com/acme\"]\n")
+        .write(
+            ".gpr.toml",
+            "denied_repositories = [\"github.com/acme/production\"]\n",
+        );
+    let output = repo.gpr(&["--assume-pushed"]);
+    assert_eq!(output.status.code(), Some(1));
+    let error = stderr(&output);
+    assert!(error.contains("github.com/owner/repo is not listed in allowed_repositories"));
+    assert!(error.contains("Refusing to open a pull request"));
+
+    // Printing the URL opens nothing and only warns
+    let output = repo.gpr(&["--print-only", "--assume-pushed"]);
+    assert_success(&output);
+    assert!(
+        stderr(&output).contains("github.com/owner/repo is not listed in allowed_repositories")
+    );
+    assert!(stdout(&output).starts_with("https://github.com/owner/repo/compare/"));
+    let output = repo.gpr(&["url", "--assume-pushed"]);
+    assert_success(&output);
+    assert!(stderr(&output).contains("is not listed in allowed_repositories"));
+
+    repo.remote("upstream", "git@github.com:acme/repo.git");
+    assert!(
+        repo.url(&[])
+            .starts_with("https://github.com/acme/repo/compare/")
+    );
+
+    repo.remote("production", "git@github.com:acme/production.git");
+    let output = repo.gpr(&["--assume-pushed", "--remote", "production"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("github.com/acme/production is listed in denied_repositories")
+    );
+
+    // The repository cannot allow itself
+    repo.write(".gpr.toml", "allowed_repositories = [\"github.com\"]\n");
+    let output = repo.gpr(&["--assume-pushed", "--remote", "origin"]);
+    assert_eq!(output.status.code(), Some(1));
+    let error = stderr(&output);
+    assert!(error.contains("Warning: ignoring allowed_repositories in"));
+    assert!(error.contains("github.com/owner/repo is not listed in allowed_repositories"));
+}